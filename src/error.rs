@@ -1,4 +1,4 @@
-use crate::novatek_gps;
+use crate::export;
 use std::io;
 use std::path::PathBuf;
 
@@ -19,12 +19,12 @@ pub enum Error {
     #[error(display = "The input path {:?} is not a file", _0)]
     PathNotFile(PathBuf),
 
-    #[error(display = "GPS error")]
-    Gps(#[error(source)] novatek_gps::Error),
-
     #[error(display = "GPX error")]
     Gpx(#[error(source)] gpx::errors::Error),
 
     #[error(display = "Glob pattern error")]
     Glob(#[error(source)] glob::PatternError),
+
+    #[error(display = "Export error")]
+    Export(#[error(source)] export::Error),
 }
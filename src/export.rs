@@ -0,0 +1,215 @@
+use crate::opts::Format;
+use crate::GpsData;
+use serde::Serialize;
+use std::io::Write;
+
+#[derive(Debug, err_derive::Error)]
+pub enum Error {
+    #[error(display = "IO error")]
+    Io(#[error(source)] std::io::Error),
+
+    #[error(display = "JSON error")]
+    Json(#[error(source)] serde_json::Error),
+
+    #[error(display = "CSV error")]
+    Csv(#[error(source)] csv::Error),
+}
+
+/// Write `gps_items` to `out` in the given non-GPX `format`
+pub fn write<W: Write>(format: Format, gps_items: &[GpsData], out: W) -> Result<(), Error> {
+    match format {
+        Format::Gpx => unreachable!("GPX output is written via the gpx crate"),
+        Format::GeoJson => write_geojson(gps_items, out),
+        Format::Csv => write_csv(gps_items, out),
+        Format::Json => write_json(gps_items, out),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FeatureCollection {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    features: Vec<Feature>,
+}
+
+#[derive(Debug, Serialize)]
+struct Feature {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    geometry: Geometry,
+    properties: Properties,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type")]
+enum Geometry {
+    Point { coordinates: [f64; 2] },
+    LineString { coordinates: Vec<[f64; 2]> },
+}
+
+/// Per-feature properties. `Fix` describes a single `Point` fix; the track-shape `LineString`
+/// has no per-vertex properties since those wouldn't describe the geometry as a whole.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum Properties {
+    Fix(FixProperties),
+    Track,
+}
+
+#[derive(Debug, Serialize)]
+struct FixProperties {
+    time: String,
+    speed_mps: f64,
+    bearing: f32,
+    source: String,
+}
+
+/// Emit one `Point` Feature per fix with scalar properties, plus a single `LineString` Feature
+/// tracing the overall track shape, so web mapping tools can both query individual fixes and
+/// render the route.
+fn write_geojson<W: Write>(gps_items: &[GpsData], out: W) -> Result<(), Error> {
+    let mut features: Vec<Feature> = gps_items
+        .iter()
+        .map(|g| Feature {
+            kind: "Feature",
+            geometry: Geometry::Point {
+                coordinates: [g.longitude, g.latitude],
+            },
+            properties: Properties::Fix(FixProperties {
+                time: g.datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+                speed_mps: g.speed,
+                bearing: g.course,
+                source: g.file_name.clone(),
+            }),
+        })
+        .collect();
+
+    if gps_items.len() > 1 {
+        features.push(Feature {
+            kind: "Feature",
+            geometry: Geometry::LineString {
+                coordinates: gps_items.iter().map(|g| [g.longitude, g.latitude]).collect(),
+            },
+            properties: Properties::Track,
+        });
+    }
+
+    let collection = FeatureCollection {
+        kind: "FeatureCollection",
+        features,
+    };
+    serde_json::to_writer_pretty(out, &collection)?;
+    Ok(())
+}
+
+fn write_csv<W: Write>(gps_items: &[GpsData], out: W) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(["time", "latitude", "longitude", "speed_mps", "bearing", "source"])?;
+    for gps in gps_items {
+        writer.write_record(&[
+            gps.datetime.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+            gps.latitude.to_string(),
+            gps.longitude.to_string(),
+            gps.speed.to_string(),
+            gps.course.to_string(),
+            gps.file_name.clone(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_json<W: Write>(gps_items: &[GpsData], out: W) -> Result<(), Error> {
+    serde_json::to_writer_pretty(out, gps_items)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gps_decoder::FixQuality;
+
+    fn gps_items() -> Vec<GpsData> {
+        vec![
+            GpsData {
+                file_name: "a.mp4".to_string(),
+                datetime: chrono::NaiveDate::from_ymd(2026, 1, 1).and_hms(0, 0, 0),
+                latitude: 1.0,
+                longitude: 2.0,
+                speed: 3.0,
+                course: 4.0,
+                sat_count: 3,
+                fix: FixQuality::ThreeDimensional,
+                discontinuous: false,
+            },
+            GpsData {
+                file_name: "a.mp4".to_string(),
+                datetime: chrono::NaiveDate::from_ymd(2026, 1, 1).and_hms(0, 0, 1),
+                latitude: 1.1,
+                longitude: 2.1,
+                speed: 3.1,
+                course: 4.1,
+                sat_count: 3,
+                fix: FixQuality::ThreeDimensional,
+                discontinuous: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn write_csv_emits_a_header_and_one_row_per_fix() {
+        let mut out = Vec::new();
+        write_csv(&gps_items(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let mut lines = text.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "time,latitude,longitude,speed_mps,bearing,source"
+        );
+        assert_eq!(lines.next().unwrap(), "2026-01-01T00:00:00Z,1,2,3,4,a.mp4");
+        assert_eq!(lines.count(), 1);
+    }
+
+    #[test]
+    fn write_json_round_trips_the_gps_items() {
+        let mut out = Vec::new();
+        write_json(&gps_items(), &mut out).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        let items = parsed.as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["file_name"], "a.mp4");
+        assert_eq!(items[0]["latitude"], 1.0);
+    }
+
+    #[test]
+    fn write_geojson_emits_a_point_per_fix_plus_a_linestring() {
+        let mut out = Vec::new();
+        write_geojson(&gps_items(), &mut out).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["type"], "FeatureCollection");
+        let features = parsed["features"].as_array().unwrap();
+        assert_eq!(features.len(), 3);
+
+        assert_eq!(features[0]["geometry"]["type"], "Point");
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([2.0, 1.0])
+        );
+        assert_eq!(features[0]["properties"]["source"], "a.mp4");
+
+        assert_eq!(features[2]["geometry"]["type"], "LineString");
+        assert_eq!(
+            features[2]["geometry"]["coordinates"].as_array().unwrap().len(),
+            2
+        );
+        assert!(features[2]["properties"].is_null());
+    }
+
+    #[test]
+    fn write_geojson_omits_the_linestring_for_a_single_fix() {
+        let mut out = Vec::new();
+        write_geojson(&gps_items()[..1], &mut out).unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(parsed["features"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,40 @@
+//! Great-circle distance helpers used for outlier rejection.
+
+/// WGS84 mean earth radius, in meters
+pub const EARTH_RADIUS_M: f64 = 6371008.8;
+
+/// Haversine great-circle distance between two lat/lon points, in meters
+pub fn haversine_distance_m(lat1_deg: f64, lon1_deg: f64, lat2_deg: f64, lon2_deg: f64) -> f64 {
+    let phi1 = lat1_deg.to_radians();
+    let phi2 = lat2_deg.to_radians();
+    let delta_phi = (lat2_deg - lat1_deg).to_radians();
+    let delta_lambda = (lon2_deg - lon1_deg).to_radians();
+
+    let a = (delta_phi / 2.0).sin().powi(2)
+        + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
+    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+
+    EARTH_RADIUS_M * c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_distance_for_identical_points() {
+        assert_eq!(haversine_distance_m(37.7749, -122.4194, 37.7749, -122.4194), 0.0);
+    }
+
+    #[test]
+    fn one_degree_of_latitude_is_about_111km() {
+        let dist_m = haversine_distance_m(0.0, 0.0, 1.0, 0.0);
+        assert!((dist_m - 111_195.0).abs() < 100.0);
+    }
+
+    #[test]
+    fn antipodal_points_are_half_the_circumference() {
+        let dist_m = haversine_distance_m(0.0, 0.0, 0.0, 180.0);
+        assert!((dist_m - std::f64::consts::PI * EARTH_RADIUS_M).abs() < 1.0);
+    }
+}
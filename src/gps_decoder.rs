@@ -0,0 +1,98 @@
+use crate::nmea_gps::{self, NmeaGps};
+use crate::novatek_gps::{self, NovatekGps};
+use chrono::NaiveDateTime;
+
+#[derive(Debug, err_derive::Error)]
+pub enum Error {
+    #[error(display = "Novatek binary GPS error")]
+    Novatek(#[error(source)] novatek_gps::Error),
+
+    #[error(display = "NMEA GPS error")]
+    Nmea(#[error(source)] nmea_gps::Error),
+}
+
+impl Error {
+    /// True when the decoder positively identified a block as having no GPS fix (lost/no sat
+    /// lock, or an NMEA sentence with its active/void status flag unset), as opposed to failing
+    /// to recognize the block at all. Callers use this to mark a track discontinuity rather than
+    /// just skipping the block.
+    pub fn is_no_fix(&self) -> bool {
+        matches!(
+            self,
+            Error::Novatek(novatek_gps::Error::NoSatLock) | Error::Nmea(nmea_gps::Error::NotActive)
+        )
+    }
+}
+
+/// A single GPS fix, normalized across decoder implementations
+#[derive(Debug, Clone)]
+pub struct GpsFix {
+    pub datetime: NaiveDateTime,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub speed_mps: f64,
+    pub bearing: f32,
+    pub sat_count: u32,
+    pub fix: FixQuality,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FixQuality {
+    TwoDimensional,
+    ThreeDimensional,
+}
+
+/// Decodes a raw `free`/`GPS ` data block into a common [`GpsFix`]
+pub trait GpsDecoder {
+    fn decode(buffer: &[u8]) -> Result<GpsFix, Error>;
+}
+
+/// Packed binary layout used by most Novatek dashcam firmwares
+pub struct NovatekBinaryDecoder;
+
+impl GpsDecoder for NovatekBinaryDecoder {
+    fn decode(buffer: &[u8]) -> Result<GpsFix, Error> {
+        let gps = NovatekGps::new(buffer)?;
+        Ok(GpsFix {
+            datetime: gps.datetime(),
+            latitude: gps.latitude_deg()?,
+            longitude: gps.longitude_deg()?,
+            speed_mps: gps.speed_mps(),
+            bearing: gps.bearing(),
+            sat_count: 3,
+            fix: FixQuality::TwoDimensional,
+        })
+    }
+}
+
+/// Raw `$GPRMC`/`$GNRMC` NMEA sentences, used by some Novatek firmware variants
+pub struct NovatekNmeaDecoder;
+
+impl GpsDecoder for NovatekNmeaDecoder {
+    fn decode(buffer: &[u8]) -> Result<GpsFix, Error> {
+        let gps = NmeaGps::parse(buffer)?;
+        Ok(GpsFix {
+            datetime: gps.datetime(),
+            latitude: gps.latitude_deg(),
+            longitude: gps.longitude_deg(),
+            speed_mps: gps.speed_mps(),
+            bearing: gps.bearing(),
+            sat_count: 0,
+            fix: FixQuality::TwoDimensional,
+        })
+    }
+}
+
+/// Try each known decoder in turn, returning the fix from whichever succeeds.
+pub fn decode_block(buffer: &[u8]) -> Result<GpsFix, Error> {
+    match NovatekBinaryDecoder::decode(buffer) {
+        Ok(fix) => return Ok(fix),
+        // The block is a valid Novatek binary layout, just without sat lock - there's no
+        // point trying to reparse these same bytes as an NMEA sentence, and the caller relies
+        // on Error::is_no_fix() to mark a track discontinuity.
+        Err(e) if e.is_no_fix() => return Err(e),
+        Err(e) => log::debug!("Novatek binary decoder failed: {}", e),
+    }
+    NovatekNmeaDecoder::decode(buffer)
+}
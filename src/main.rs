@@ -2,18 +2,23 @@
 // #![deny(warnings, clippy::all)]
 
 use crate::error::Error;
-use crate::novatek_gps::NovatekGps;
-use crate::opts::{Opts, SortingMode};
+use crate::geo;
+use crate::gps_decoder::{self, FixQuality};
+use crate::opts::{Format, Opts, SortingMode};
 use glob::glob;
 use gpx::*;
 use mp4::{Mp4Box, Mp4Reader};
 use std::collections::BTreeSet;
 use std::fs::File;
 use std::io::{BufReader, Read, Seek, SeekFrom};
-use std::{fmt, process};
+use std::process;
 use structopt::StructOpt;
 
 mod error;
+mod export;
+mod geo;
+mod gps_decoder;
+mod nmea_gps;
 mod novatek_gps;
 mod opts;
 
@@ -37,6 +42,12 @@ fn do_main() -> Result<(), Error> {
         return Err(Error::OutputFileExists(opts.output));
     }
 
+    let creation_time = chrono::Utc::now();
+    log::info!(
+        "Generating GPX metadata with creation time {} (stored as UTC)",
+        opts.timezone.format(creation_time.naive_utc())
+    );
+
     let mut gpx = Gpx::default();
     gpx.version = GpxVersion::Gpx11;
     gpx.creator = env!("CARGO_PKG_NAME").to_string().into();
@@ -48,7 +59,8 @@ fn do_main() -> Result<(), Error> {
             .to_string_lossy()
             .to_string()
             .into(),
-        time: chrono::Utc::now().into(),
+        // See Opts::timezone: GPX <time> values are always stored in UTC.
+        time: creation_time.into(),
         ..Default::default()
     }
     .into();
@@ -89,6 +101,7 @@ fn do_main() -> Result<(), Error> {
         log::info!("Loaded '{}', {}", file_name, gps_box.summary()?);
 
         let mut reader = mp4.into_inner();
+        let mut lock_lost = false;
 
         for (idx, b) in gps_box.data_blocks.iter().enumerate() {
             log::debug!("[{}] 0x{:08X}, size={}", idx, b.offset, b.size,);
@@ -99,8 +112,8 @@ fn do_main() -> Result<(), Error> {
             buf.resize(b.size as usize, 0);
             reader.read_exact(&mut buf)?;
 
-            let gps = match NovatekGps::new(&buf[..]) {
-                Ok(gps) => gps,
+            let fix = match gps_decoder::decode_block(&buf[..]) {
+                Ok(fix) => fix,
                 Err(e) => {
                     log::warn!(
                         "Skipping GPS block [{}] at offset 0x{:08X} size={}: {}",
@@ -109,19 +122,33 @@ fn do_main() -> Result<(), Error> {
                         b.size,
                         e,
                     );
+                    if e.is_no_fix() {
+                        lock_lost = true;
+                    }
                     continue;
                 }
             };
 
             let gps_data = GpsData {
                 file_name: file_name.clone(),
-                datetime: gps.datetime(),
-                latitude: gps.latitude_deg()?,
-                longitude: gps.longitude_deg()?,
-                speed: gps.speed_mps(),
-                course: gps.bearing(),
+                datetime: fix.datetime,
+                latitude: fix.latitude,
+                longitude: fix.longitude,
+                speed: fix.speed_mps,
+                course: fix.bearing,
+                sat_count: fix.sat_count,
+                fix: fix.fix,
+                discontinuous: lock_lost,
             };
-            log::info!("{}", gps_data);
+            lock_lost = false;
+            log::info!(
+                "{} ({}, {}) {:.02} m/s {:.02}°",
+                opts.timezone.format(gps_data.datetime),
+                gps_data.latitude,
+                gps_data.longitude,
+                gps_data.speed,
+                gps_data.course
+            );
             gps_items.push(gps_data);
         }
     }
@@ -132,59 +159,227 @@ fn do_main() -> Result<(), Error> {
         SortingMode::None => (),
     }
 
-    // TODO - filter outliers, getting some oddball coordinates in the mix
-    // add a flag to opts for it
-    // might be doing something dumb on the conversions
-    // speed, lat/lon
-    // 2021-08-09 08:15:26 (47.669230143229164, -117.11126302083333)
-    // 2021-08-09 08:15:27 (47.669230143229164, -0.9333333333333333)
-    // 2021-08-09 08:15:28 (47.669230143229164, -117.11126302083333)
-
-    // TODO - segment the TrackSegments when GPS data sat lock is not valid
-    // currently single Track with all items in a single TrackSegment
-    // fill out all the Waypoint fields
-    let points = gps_items
-        .into_iter()
-        .map(|gps| {
-            let mut wp = Waypoint::new((gps.longitude, gps.latitude).into());
-            // TODO timezone in opts
-            wp.time = chrono::DateTime::from_utc(gps.datetime, chrono::Utc).into();
-            wp.source = gps.file_name.into();
-            wp.speed = gps.speed.into();
-            wp.fix = Fix::TwoDimensional.into();
-            wp.sat = 3.into();
-            wp
-        })
-        .collect();
-
-    let segment = TrackSegment { points };
-
-    gpx.tracks = vec![Track {
-        segments: vec![segment],
-        ..Default::default()
-    }];
+    if opts.filter_outliers {
+        let before = gps_items.len();
+        let keep = outlier_keep_mask(&gps_items, opts.max_speed);
+        let mut idx = 0;
+        gps_items.retain(|_| {
+            let keep = keep[idx];
+            idx += 1;
+            keep
+        });
+        let removed = before - gps_items.len();
+        if removed > 0 {
+            log::info!(
+                "Filtered {} outlier GPS fix(es) exceeding {:.02} m/s",
+                removed,
+                opts.max_speed
+            );
+        }
+    }
+
+    match opts.format {
+        Format::Gpx => {
+            gpx.tracks = vec![Track {
+                segments: segment_tracks(gps_items, opts.gap_seconds),
+                ..Default::default()
+            }];
 
-    gpx::write(&gpx, output_file)?;
+            gpx::write(&gpx, output_file)?;
+        }
+        format => export::write(format, &gps_items, output_file)?,
+    }
 
     Ok(())
 }
 
-#[derive(Debug, Clone)]
-struct GpsData {
-    file_name: String,
-    datetime: chrono::NaiveDateTime,
-    latitude: f64,
-    longitude: f64,
-    speed: f64,
-    course: f32,
+/// Split `gps_items` into `TrackSegment`s, starting a new one on a time gap exceeding
+/// `gap_seconds`, a change of source file, or a discontinuity left by a skipped no-sat-lock
+/// block, so parking events and file boundaries don't get bridged by a single straight line.
+fn segment_tracks(gps_items: Vec<GpsData>, gap_seconds: f64) -> Vec<TrackSegment> {
+    let mut segments: Vec<TrackSegment> = Vec::new();
+    let mut current_points: Vec<Waypoint> = Vec::new();
+    let mut prev_datetime: Option<chrono::NaiveDateTime> = None;
+    let mut prev_file_name: Option<String> = None;
+
+    for gps in gps_items.into_iter() {
+        let start_new_segment = match (prev_datetime, &prev_file_name) {
+            (Some(prev_dt), Some(prev_fn)) => {
+                let dt_secs = (gps.datetime - prev_dt).num_milliseconds() as f64 / 1000.0;
+                dt_secs.abs() > gap_seconds || &gps.file_name != prev_fn || gps.discontinuous
+            }
+            _ => false,
+        };
+
+        if start_new_segment && !current_points.is_empty() {
+            segments.push(TrackSegment {
+                points: std::mem::take(&mut current_points),
+            });
+        }
+
+        prev_datetime = Some(gps.datetime);
+        prev_file_name = Some(gps.file_name.clone());
+
+        // See Opts::timezone: GPX <time> values are always stored in UTC.
+        let mut wp = Waypoint::new((gps.longitude, gps.latitude).into());
+        wp.time = chrono::DateTime::from_utc(gps.datetime, chrono::Utc).into();
+        wp.source = gps.file_name.into();
+        wp.speed = gps.speed.into();
+        wp.fix = match gps.fix {
+            FixQuality::TwoDimensional => Fix::TwoDimensional,
+            FixQuality::ThreeDimensional => Fix::ThreeDimensional,
+        }
+        .into();
+        wp.sat = (gps.sat_count as i32).into();
+        current_points.push(wp);
+    }
+    if !current_points.is_empty() {
+        segments.push(TrackSegment {
+            points: current_points,
+        });
+    }
+
+    segments
 }
 
-impl fmt::Display for GpsData {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{} ({}, {}) {:.02} m/s {:.02}Â°",
-            self.datetime, self.latitude, self.longitude, self.speed, self.course
-        )
+/// Compute, for each fix in `items`, whether it should be kept after rejecting outliers whose
+/// implied velocity from the previous accepted fix (in GPS date order) exceeds `max_speed_mps`,
+/// using the haversine great-circle distance between consecutive points. Fixes separated by a
+/// zero-second gap are instead checked against a raw coordinate delta threshold.
+///
+/// The result is indexed by `items`' original order, so callers can filter without disturbing
+/// the sort order the rest of `do_main` relies on (e.g. `--sort file`/`--sort none`).
+fn outlier_keep_mask(items: &[GpsData], max_speed_mps: f64) -> Vec<bool> {
+    const ZERO_GAP_MAX_COORD_DELTA_DEG: f64 = 0.01;
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&i| items[i].datetime);
+
+    let mut keep = vec![false; items.len()];
+    let mut prev: Option<usize> = None;
+    for i in order {
+        let item = &items[i];
+        let accept = match prev {
+            None => true,
+            Some(prev_i) => {
+                let prev = &items[prev_i];
+                let dt_secs = (item.datetime - prev.datetime).num_milliseconds() as f64 / 1000.0;
+                if dt_secs <= 0.0 {
+                    (item.latitude - prev.latitude).abs() <= ZERO_GAP_MAX_COORD_DELTA_DEG
+                        && (item.longitude - prev.longitude).abs() <= ZERO_GAP_MAX_COORD_DELTA_DEG
+                } else {
+                    let dist_m = geo::haversine_distance_m(
+                        prev.latitude,
+                        prev.longitude,
+                        item.latitude,
+                        item.longitude,
+                    );
+                    (dist_m / dt_secs) <= max_speed_mps
+                }
+            }
+        };
+
+        if accept {
+            keep[i] = true;
+            prev = Some(i);
+        }
+    }
+    keep
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub(crate) struct GpsData {
+    pub(crate) file_name: String,
+    pub(crate) datetime: chrono::NaiveDateTime,
+    pub(crate) latitude: f64,
+    pub(crate) longitude: f64,
+    pub(crate) speed: f64,
+    pub(crate) course: f32,
+    pub(crate) sat_count: u32,
+    pub(crate) fix: FixQuality,
+    /// Set when this fix immediately follows a block that was skipped due to no sat lock,
+    /// so the track segmenter can start a new segment at the discontinuity
+    pub(crate) discontinuous: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn gps(file_name: &str, secs: i64, latitude: f64, longitude: f64, discontinuous: bool) -> GpsData {
+        GpsData {
+            file_name: file_name.to_string(),
+            datetime: NaiveDate::from_ymd(2026, 1, 1).and_hms(0, 0, 0) + chrono::Duration::seconds(secs),
+            latitude,
+            longitude,
+            speed: 0.0,
+            course: 0.0,
+            sat_count: 3,
+            fix: FixQuality::ThreeDimensional,
+            discontinuous,
+        }
+    }
+
+    #[test]
+    fn outlier_keep_mask_rejects_an_implausible_jump() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("a.mp4", 1, 10.0, 10.0, false),
+            gps("a.mp4", 2, 0.0001, 0.0001, false),
+        ];
+        assert_eq!(outlier_keep_mask(&items, 80.0), vec![true, false, true]);
+    }
+
+    #[test]
+    fn outlier_keep_mask_keeps_a_plausible_track() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("a.mp4", 1, 0.0001, 0.0001, false),
+            gps("a.mp4", 2, 0.0002, 0.0002, false),
+        ];
+        assert_eq!(outlier_keep_mask(&items, 80.0), vec![true, true, true]);
+    }
+
+    #[test]
+    fn segment_tracks_splits_on_time_gap() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("a.mp4", 100, 0.0001, 0.0001, false),
+        ];
+        let segments = segment_tracks(items, 10.0);
+        assert_eq!(segments.len(), 2);
+    }
+
+    #[test]
+    fn segment_tracks_splits_on_file_change() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("b.mp4", 1, 0.0001, 0.0001, false),
+        ];
+        let segments = segment_tracks(items, 10.0);
+        assert_eq!(segments.len(), 2);
+    }
+
+    #[test]
+    fn segment_tracks_splits_on_discontinuity() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("a.mp4", 1, 0.0001, 0.0001, true),
+        ];
+        let segments = segment_tracks(items, 10.0);
+        assert_eq!(segments.len(), 2);
+    }
+
+    #[test]
+    fn segment_tracks_keeps_contiguous_points_in_one_segment() {
+        let items = vec![
+            gps("a.mp4", 0, 0.0, 0.0, false),
+            gps("a.mp4", 1, 0.0001, 0.0001, false),
+            gps("a.mp4", 2, 0.0002, 0.0002, false),
+        ];
+        let segments = segment_tracks(items, 10.0);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].points.len(), 3);
     }
 }
@@ -0,0 +1,267 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use std::str;
+
+#[derive(Debug, Clone, Eq, PartialEq, err_derive::Error)]
+pub enum Error {
+    #[error(display = "No supported NMEA sentence found in buffer")]
+    NoSentence,
+
+    #[error(display = "UTF8 error")]
+    Utf8(#[error(source)] str::Utf8Error),
+
+    #[error(display = "Checksum mismatch, expected 0x{:02X} got 0x{:02X}", _0, _1)]
+    ChecksumMismatch(u8, u8),
+
+    #[error(display = "Invalid or missing field '{}'", _0)]
+    InvalidField(&'static str),
+
+    #[error(display = "GPS receiver does not have a valid fix")]
+    NotActive,
+}
+
+/// A single fix parsed from a `$GPRMC`/`$GNRMC` NMEA sentence
+#[derive(Debug, Clone)]
+pub struct NmeaGps {
+    datetime: NaiveDateTime,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    speed_mps: f64,
+    bearing: f32,
+}
+
+impl NmeaGps {
+    const KNOTS_TO_MPS: f64 = 0.514444;
+
+    /// Scan `buffer` for a `$GP`/`$GN` RMC sentence and parse it into a fix. Some Novatek
+    /// firmware variants store raw ASCII NMEA sentences in the `free`/`GPS ` box instead of the
+    /// packed binary layout used by [`crate::novatek_gps::NovatekGps`]. Returns
+    /// [`Error::NotActive`] when no active fix is found but at least one RMC sentence reports a
+    /// void status.
+    pub fn parse(buffer: &[u8]) -> Result<NmeaGps, Error> {
+        let mut saw_void = false;
+        for sentence in Self::sentences(buffer) {
+            match Self::parse_sentence(sentence) {
+                Ok(gps) => return Ok(gps),
+                Err(Error::NotActive) => saw_void = true,
+                Err(_) => (),
+            }
+        }
+        Err(if saw_void {
+            Error::NotActive
+        } else {
+            Error::NoSentence
+        })
+    }
+
+    fn sentences(buffer: &[u8]) -> impl Iterator<Item = &[u8]> {
+        buffer
+            .split(|&b| b == b'\r' || b == b'\n' || b == 0)
+            .filter(|line| line.first() == Some(&b'$'))
+    }
+
+    fn parse_sentence(line: &[u8]) -> Result<NmeaGps, Error> {
+        let line = str::from_utf8(line)?;
+
+        let (body, checksum_str) = line
+            .strip_prefix('$')
+            .and_then(|rest| rest.split_once('*'))
+            .ok_or(Error::InvalidField("sentence"))?;
+
+        let expected = u8::from_str_radix(checksum_str.get(..2).unwrap_or(""), 16)
+            .map_err(|_| Error::InvalidField("checksum"))?;
+        let computed = body.bytes().fold(0u8, |acc, b| acc ^ b);
+        if computed != expected {
+            return Err(Error::ChecksumMismatch(expected, computed));
+        }
+
+        let mut fields = body.split(',');
+        let sentence_type = fields.next().ok_or(Error::InvalidField("sentence type"))?;
+        let talker = sentence_type.get(0..2).ok_or(Error::InvalidField("talker"))?;
+        if talker != "GP" && talker != "GN" {
+            return Err(Error::InvalidField("talker"));
+        }
+        if sentence_type.get(2..5) != Some("RMC") {
+            return Err(Error::InvalidField("sentence type"));
+        }
+
+        let time = fields.next().ok_or(Error::InvalidField("time"))?;
+        let status = fields.next().ok_or(Error::InvalidField("status"))?;
+        let lat = fields.next().ok_or(Error::InvalidField("latitude"))?;
+        let lat_hemi = fields.next().ok_or(Error::InvalidField("latitude hemisphere"))?;
+        let lon = fields.next().ok_or(Error::InvalidField("longitude"))?;
+        let lon_hemi = fields.next().ok_or(Error::InvalidField("longitude hemisphere"))?;
+        let speed_knots = fields.next().ok_or(Error::InvalidField("speed"))?;
+        let track_angle = fields.next().ok_or(Error::InvalidField("track angle"))?;
+        let date = fields.next().ok_or(Error::InvalidField("date"))?;
+
+        if status != "A" {
+            return Err(Error::NotActive);
+        }
+
+        let datetime = Self::parse_datetime(time, date)?;
+        let mut latitude_deg = Self::parse_coordinate(lat, 2)?;
+        if lat_hemi == "S" {
+            latitude_deg = -latitude_deg;
+        }
+        let mut longitude_deg = Self::parse_coordinate(lon, 3)?;
+        if lon_hemi == "W" {
+            longitude_deg = -longitude_deg;
+        }
+        let speed_mps = speed_knots
+            .parse::<f64>()
+            .map_err(|_| Error::InvalidField("speed"))?
+            * Self::KNOTS_TO_MPS;
+        let bearing = track_angle
+            .parse::<f32>()
+            .map_err(|_| Error::InvalidField("track angle"))?;
+
+        Ok(NmeaGps {
+            datetime,
+            latitude_deg,
+            longitude_deg,
+            speed_mps,
+            bearing,
+        })
+    }
+
+    /// `ddmm.mmmm`/`dddmm.mmmm`, `deg_digits` is 2 for latitude and 3 for longitude
+    fn parse_coordinate(raw: &str, deg_digits: usize) -> Result<f64, Error> {
+        let deg: f64 = raw
+            .get(..deg_digits)
+            .ok_or(Error::InvalidField("coordinate"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("coordinate"))?;
+        let min: f64 = raw
+            .get(deg_digits..)
+            .ok_or(Error::InvalidField("coordinate"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("coordinate"))?;
+        Ok(deg + min / 60.0)
+    }
+
+    fn parse_datetime(time: &str, date: &str) -> Result<NaiveDateTime, Error> {
+        let hour: u32 = time
+            .get(0..2)
+            .ok_or(Error::InvalidField("hour"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("hour"))?;
+        let minute: u32 = time
+            .get(2..4)
+            .ok_or(Error::InvalidField("minute"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("minute"))?;
+        let second: u32 = time
+            .get(4..6)
+            .ok_or(Error::InvalidField("second"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("second"))?;
+        let day: u32 = date
+            .get(0..2)
+            .ok_or(Error::InvalidField("day"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("day"))?;
+        let month: u32 = date
+            .get(2..4)
+            .ok_or(Error::InvalidField("month"))?
+            .parse()
+            .map_err(|_| Error::InvalidField("month"))?;
+        let year: i32 = 2000
+            + date
+                .get(4..6)
+                .ok_or(Error::InvalidField("year"))?
+                .parse::<i32>()
+                .map_err(|_| Error::InvalidField("year"))?;
+        let date = NaiveDate::from_ymd_opt(year, month, day).ok_or(Error::InvalidField("date"))?;
+        date.and_hms_opt(hour, minute, second)
+            .ok_or(Error::InvalidField("time"))
+    }
+
+    #[inline]
+    pub fn datetime(&self) -> NaiveDateTime {
+        self.datetime
+    }
+
+    #[inline]
+    pub fn latitude_deg(&self) -> f64 {
+        self.latitude_deg
+    }
+
+    #[inline]
+    pub fn longitude_deg(&self) -> f64 {
+        self.longitude_deg
+    }
+
+    #[inline]
+    pub fn speed_mps(&self) -> f64 {
+        self.speed_mps
+    }
+
+    #[inline]
+    pub fn bearing(&self) -> f32 {
+        self.bearing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID_SENTENCE: &[u8] =
+        b"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394*11\r\n";
+
+    #[test]
+    fn parses_a_valid_rmc_sentence() {
+        let gps = NmeaGps::parse(VALID_SENTENCE).unwrap();
+        assert_eq!(gps.datetime(), NaiveDate::from_ymd(2094, 3, 23).and_hms(12, 35, 19));
+        assert!((gps.latitude_deg() - 48.1173).abs() < 1e-4);
+        assert!((gps.longitude_deg() - 11.5166).abs() < 1e-4);
+        assert!((gps.bearing() - 84.4).abs() < 1e-3);
+    }
+
+    #[test]
+    fn rejects_a_checksum_mismatch() {
+        let sentence = b"$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394*00\r\n";
+        assert!(matches!(
+            NmeaGps::parse(sentence),
+            Err(Error::NoSentence)
+        ));
+    }
+
+    #[test]
+    fn rejects_a_void_fix() {
+        let sentence = b"$GPRMC,123519,V,4807.038,N,01131.000,E,022.4,084.4,230394*06\r\n";
+        assert!(matches!(NmeaGps::parse(sentence), Err(Error::NotActive)));
+    }
+
+    #[test]
+    fn parse_coordinate_rejects_short_input() {
+        assert!(matches!(
+            NmeaGps::parse_coordinate("1", 2),
+            Err(Error::InvalidField("coordinate"))
+        ));
+    }
+
+    #[test]
+    fn parse_datetime_rejects_short_input() {
+        assert!(matches!(
+            NmeaGps::parse_datetime("12", "230394"),
+            Err(Error::InvalidField("minute"))
+        ));
+    }
+
+    #[test]
+    fn parse_datetime_rejects_an_out_of_range_date() {
+        assert!(matches!(
+            NmeaGps::parse_datetime("123519", "329934"),
+            Err(Error::InvalidField("date"))
+        ));
+    }
+
+    #[test]
+    fn parse_datetime_rejects_an_out_of_range_time() {
+        assert!(matches!(
+            NmeaGps::parse_datetime("996199", "230394"),
+            Err(Error::InvalidField("time"))
+        ));
+    }
+}
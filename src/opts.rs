@@ -27,6 +27,30 @@ pub struct Opts {
     #[structopt(short = "s", long, default_value)]
     pub sort: SortingMode,
 
+    /// Filter out GPS fixes whose implied speed from the previous accepted fix exceeds
+    /// --max-speed
+    #[structopt(long)]
+    pub filter_outliers: bool,
+
+    /// Maximum allowed speed between consecutive fixes, in m/s, used by --filter-outliers
+    #[structopt(long, default_value = "80.0")]
+    pub max_speed: f64,
+
+    /// Start a new GPX track segment when the time gap between consecutive fixes exceeds this
+    /// many seconds
+    #[structopt(long, default_value = "10.0")]
+    pub gap_seconds: f64,
+
+    /// Output format (gpx, geojson, csv, json)
+    #[structopt(long, default_value)]
+    pub format: Format,
+
+    /// Timezone used for displaying GPS fix log lines, as a fixed UTC offset (e.g. "-07:00")
+    /// or an IANA time zone name (e.g. "America/Los_Angeles"). Novatek fixes are recorded in
+    /// UTC and the stored GPX `<time>` always remains UTC regardless of this setting.
+    #[structopt(long, default_value)]
+    pub timezone: TimeZone,
+
     /// Input file path or glob pattern
     #[structopt(name = "input path or glob pattern")]
     pub input: String,
@@ -70,3 +94,162 @@ impl FromStr for SortingMode {
         })
     }
 }
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Format {
+    /// GPX (GPS Exchange Format)
+    Gpx,
+    /// GeoJSON FeatureCollection
+    GeoJson,
+    /// CSV, one row per GPS fix
+    Csv,
+    /// Raw JSON array of GPS fixes
+    Json,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::Gpx
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Format::Gpx => f.write_str("gpx"),
+            Format::GeoJson => f.write_str("geojson"),
+            Format::Csv => f.write_str("csv"),
+            Format::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "gpx" => Format::Gpx,
+            "geojson" => Format::GeoJson,
+            "csv" => Format::Csv,
+            "json" => Format::Json,
+            _ => return Err("Unsupported output format".to_string()),
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum TimeZone {
+    Utc,
+    FixedOffset(chrono::FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+impl TimeZone {
+    /// Format a naive UTC datetime as it would appear in this timezone
+    pub fn format(&self, naive_utc: chrono::NaiveDateTime) -> String {
+        let utc = chrono::DateTime::<chrono::Utc>::from_utc(naive_utc, chrono::Utc);
+        match self {
+            TimeZone::Utc => utc.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            TimeZone::FixedOffset(offset) => utc
+                .with_timezone(offset)
+                .format("%Y-%m-%d %H:%M:%S %z")
+                .to_string(),
+            TimeZone::Named(tz) => utc
+                .with_timezone(tz)
+                .format("%Y-%m-%d %H:%M:%S %Z")
+                .to_string(),
+        }
+    }
+
+    /// Parse a `(+|-)HH:MM` fixed UTC offset, e.g. "-07:00"
+    fn parse_fixed_offset(s: &str) -> Option<chrono::FixedOffset> {
+        let bytes = s.as_bytes();
+        if bytes.len() != 6 || bytes[3] != b':' {
+            return None;
+        }
+        let sign: i32 = match bytes[0] {
+            b'+' => 1,
+            b'-' => -1,
+            _ => return None,
+        };
+        let hours: i32 = s.get(1..3)?.parse().ok()?;
+        let minutes: i32 = s.get(4..6)?.parse().ok()?;
+        chrono::FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+impl Default for TimeZone {
+    fn default() -> Self {
+        TimeZone::Utc
+    }
+}
+
+impl fmt::Display for TimeZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeZone::Utc => f.write_str("UTC"),
+            TimeZone::FixedOffset(offset) => write!(f, "{}", offset),
+            TimeZone::Named(tz) => write!(f, "{}", tz),
+        }
+    }
+}
+
+impl FromStr for TimeZone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("utc") {
+            return Ok(TimeZone::Utc);
+        }
+        if let Some(offset) = Self::parse_fixed_offset(s) {
+            return Ok(TimeZone::FixedOffset(offset));
+        }
+        if let Ok(tz) = s.parse::<chrono_tz::Tz>() {
+            return Ok(TimeZone::Named(tz));
+        }
+        Err(format!(
+            "Unsupported timezone '{}', expected \"UTC\", a fixed offset like \"-07:00\", \
+             or an IANA zone name like \"America/Los_Angeles\"",
+            s
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_utc() {
+        assert!(matches!("UTC".parse::<TimeZone>(), Ok(TimeZone::Utc)));
+        assert!(matches!("utc".parse::<TimeZone>(), Ok(TimeZone::Utc)));
+    }
+
+    #[test]
+    fn parses_a_negative_fixed_offset() {
+        let offset = TimeZone::parse_fixed_offset("-07:00").unwrap();
+        assert_eq!(offset.local_minus_utc(), -7 * 3600);
+    }
+
+    #[test]
+    fn parses_a_positive_fixed_offset() {
+        let offset = TimeZone::parse_fixed_offset("+05:30").unwrap();
+        assert_eq!(offset.local_minus_utc(), 5 * 3600 + 30 * 60);
+    }
+
+    #[test]
+    fn rejects_a_malformed_fixed_offset() {
+        assert!(TimeZone::parse_fixed_offset("0700").is_none());
+        assert!(TimeZone::parse_fixed_offset("+7:00").is_none());
+        assert!(TimeZone::parse_fixed_offset("~07:00").is_none());
+    }
+
+    #[test]
+    fn parses_a_named_zone() {
+        assert!(matches!(
+            "America/Los_Angeles".parse::<TimeZone>(),
+            Ok(TimeZone::Named(_))
+        ));
+    }
+}